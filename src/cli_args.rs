@@ -1,4 +1,5 @@
 extern crate quicli;
+use crate::project::ConfigOverride;
 use quicli::prelude::*;
 use std::path::PathBuf;
 
@@ -11,14 +12,18 @@ pub struct CliArgs {
     #[structopt(flatten)]
     pub verbosity: Verbosity,
 
-    /// The project file
-    #[structopt(
-        short = "p",
-        long = "project",
-        default_value = "onyx.yml",
-        parse(from_os_str)
-    )]
-    pub project_file: PathBuf,
+    /// The project file. If not given, it is discovered by looking for `onyx.yml`, `onyx.yaml`,
+    /// `onyx.json`, `onyx.toml` or `onyx.ron` in the current directory and its parents
+    /// (`init` does not discover; it creates `onyx.yml` in the current directory)
+    #[structopt(short = "p", long = "project", parse(from_os_str))]
+    pub project_file: Option<PathBuf>,
+
+    /// Override a config value for this invocation: `key=value` or `key.sub_key=value` for the
+    /// default app, or prefix with `app:` to target a specific app instead, e.g.
+    /// `myapp:port=9999` or `myapp:db.port=9999`. Applied after the file and its includes are
+    /// merged, so it always wins. Can be given multiple times
+    #[structopt(long = "set")]
+    pub overrides: Vec<ConfigOverride>,
 
     #[structopt(subcommand)]
     pub cmd: CliCommand,
@@ -45,11 +50,29 @@ pub enum CliCommand {
         #[structopt(short = "a", long = "app")]
         app: Option<String>,
 
-        /// The key to search
-        key: String,
+        /// Export the merged config as dotenv `KEY=value` lines instead of searching a single key
+        #[structopt(long = "env")]
+        env: bool,
+
+        /// Separator used to join nested keys when exporting with `--env`
+        #[structopt(long = "separator", default_value = "_")]
+        separator: String,
+
+        /// Write the `--env` output to this file instead of stdout
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        /// The key to search. Not used with `--env`
+        #[structopt(required_unless = "env")]
+        key: Option<String>,
 
         #[structopt(name = "sub-key")]
         /// The subkey to search. Raises error if `key` doesn't contain key-value pairs
         sub_key: Option<String>,
     },
+
+    /// Prints the tool version, the supported project-schema version range and the detected
+    /// container/language of the loaded project
+    #[structopt(name = "version")]
+    Version,
 }
@@ -3,33 +3,48 @@
 extern crate failure;
 extern crate promptly;
 extern crate quicli;
+extern crate ron;
 extern crate serde;
 extern crate serde_derive;
+extern crate serde_json;
 extern crate serde_yaml;
+extern crate toml;
 extern crate void;
 use quicli::prelude::*;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
 
 mod cli_args;
 mod project;
 pub use cli_args::*;
 use project::*;
 
+const DEFAULT_PROJECT_FILE: &str = "onyx.yml";
+const PROJECT_FILE_STEM: &str = "onyx";
+
 pub fn process(args: &CliArgs) -> Result<()> {
     debug!("Processed args: {:#?}", &args);
-    let load = || -> Result<Project> {
-        let project = Project::load(&args.project_file)?;
+    let load = |project_file: &PathBuf| -> Result<Project> {
+        let project = Project::load(project_file)?;
         debug!("Project: {:#?}", &project);
-        let merged = project.merge()?;
+        let merged = project.merge(project_file, &args.overrides)?;
         debug!("Merged: {:#?}", &merged);
         Ok(merged)
     };
 
     match args.cmd {
         CliCommand::Init { ref name } => {
-            init(&args.project_file, &name)?;
+            let project_file = args
+                .project_file
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_PROJECT_FILE));
+            init(&project_file, &name)?;
         }
         CliCommand::Run { ref entries } => {
-            let project = load()?;
+            let project_file = discover_project_file(&args.project_file)?;
+            let project = load(&project_file)?;
             let to_run = project.runner.entries_to_run(entries)?;
             println!(
                 "{}",
@@ -42,14 +57,59 @@ pub fn process(args: &CliArgs) -> Result<()> {
         }
         CliCommand::Config {
             ref app,
+            env,
+            ref separator,
+            ref output,
             ref key,
             ref sub_key,
         } => {
-            let project = load()?;
-            let result = project.get_config(app, key, sub_key)?;
-            println!("{}", result);
+            let project_file = discover_project_file(&args.project_file)?;
+            let project = load(&project_file)?;
+
+            if env {
+                let dotenv = project.to_dotenv(app, separator)?;
+                match output {
+                    Some(path) => {
+                        let mut file = File::create(path)?;
+                        file.write_all(dotenv.as_bytes())?;
+                    }
+                    None => println!("{}", dotenv),
+                }
+            } else {
+                let key = key.as_ref().expect("key is required unless --env is set");
+                let result = project.get_config(app, key, sub_key)?;
+                println!("{}", result);
+            }
+        }
+        CliCommand::Version => {
+            let project_file = discover_project_file(&args.project_file)?;
+            let project = Project::load(&project_file)?;
+
+            println!("onyx {}", env!("CARGO_PKG_VERSION"));
+            println!(
+                "Supported project-schema versions: {}-{}",
+                MIN_SCHEMA_VERSION, CURRENT_SCHEMA_VERSION
+            );
+            println!("Container: {:?}", project.container);
+            println!(
+                "Language: {}",
+                project
+                    .language
+                    .map(|language| format!("{:?}", language))
+                    .unwrap_or_else(|| "none".to_string())
+            );
         }
     }
 
     Ok(())
 }
+
+fn discover_project_file(explicit: &Option<PathBuf>) -> Result<PathBuf> {
+    match explicit {
+        Some(path) => Ok(path.clone()),
+        None => {
+            let cwd = env::current_dir()?;
+            find_project_file(&cwd, PROJECT_FILE_STEM, ProjectFormat::extensions())
+        }
+    }
+}
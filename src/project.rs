@@ -1,14 +1,14 @@
 use quicli::prelude::*;
 use std::collections::HashMap;
 use std::convert::From;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::ErrorKind;
 use std::io::Write;
 use std::marker::PhantomData;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::result::Result as Res;
 use std::str::FromStr;
 
@@ -21,6 +21,9 @@ use void::Void;
 pub struct Project {
     pub name: String,
 
+    #[serde(default)]
+    pub version: u32,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
@@ -37,7 +40,7 @@ pub struct Project {
     pub include: Vec<PathBuf>,
 
     #[serde(skip)]
-    pub included: Option<Vec<ProjectInclude>>,
+    pub included: Option<Vec<WithPath<ProjectInclude>>>,
 
     #[serde(default, skip_serializing_if = "Application::omit_ser")]
     pub app: Application,
@@ -49,6 +52,18 @@ pub struct Project {
     pub runner: Runner,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithPath<T> {
+    pub value: T,
+    pub path: PathBuf,
+}
+
+impl<T> WithPath<T> {
+    pub fn new(value: T, path: PathBuf) -> Self {
+        WithPath { value, path }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectInclude {
     #[serde(default)]
@@ -120,6 +135,79 @@ pub enum ConfigSearch {
     Multiple(HashMap<String, Config>),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    pub app: Option<String>,
+    pub key: String,
+    pub sub_key: Option<String>,
+    pub value: String,
+}
+
+impl FromStr for ConfigOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Res<Self, Self::Err> {
+        let mut parts = s.splitn(2, '=');
+        let path = parts.next().unwrap_or("");
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("Invalid --set override {:?}, expected `key=value`", s))?;
+
+        let (app, path) = match path.splitn(2, ':').collect::<Vec<&str>>().as_slice() {
+            [app, rest] => (Some((*app).to_string()), *rest),
+            [rest] => (None, *rest),
+            _ => unreachable!(),
+        };
+
+        let segments: Vec<&str> = path.split('.').collect();
+        let (key, sub_key) = match segments.as_slice() {
+            [key] => ((*key).to_string(), None),
+            [key, sub_key] => ((*key).to_string(), Some((*sub_key).to_string())),
+            _ => {
+                return Err(format!(
+                    "Invalid --set key {:?}, expected `key` or `key.sub_key`",
+                    path
+                ))
+            }
+        };
+
+        Ok(ConfigOverride {
+            app,
+            key,
+            sub_key,
+            value: value.to_string(),
+        })
+    }
+}
+
+pub struct MergeCtx<'a> {
+    pub self_path: &'a Path,
+    pub other_path: &'a Path,
+    pub key: Option<String>,
+}
+
+impl<'a> MergeCtx<'a> {
+    pub fn new(self_path: &'a Path, other_path: &'a Path) -> Self {
+        MergeCtx {
+            self_path,
+            other_path,
+            key: None,
+        }
+    }
+
+    pub fn with_key(&self, key: &str) -> Self {
+        MergeCtx {
+            self_path: self.self_path,
+            other_path: self.other_path,
+            key: Some(key.to_string()),
+        }
+    }
+}
+
+pub trait Merge {
+    fn merge(&mut self, other: Self, ctx: &MergeCtx) -> Result<()>;
+}
+
 #[derive(Debug, Fail)]
 enum ProjectError {
     #[fail(display = "Unsupported file format: {:?}", ext)]
@@ -129,22 +217,98 @@ enum ProjectError {
     ExistingFile { path: OsString },
 
     #[fail(
-        display = "Incompatible config value types (found string and map). Key: {}",
-        key
+        display = "{} in {:?} conflicts with {} in {:?} for key `{}`",
+        self_kind, self_path, other_kind, other_path, key
     )]
-    IncompatibleConfigType { key: String },
+    IncompatibleConfigType {
+        key: String,
+        self_kind: &'static str,
+        self_path: PathBuf,
+        other_kind: &'static str,
+        other_path: PathBuf,
+    },
 
     #[fail(display = "Error while parsing file {:?}: {}", path, error)]
-    FileParseError {
-        path: PathBuf,
-        error: serde_yaml::Error,
-    },
+    FileParseError { path: PathBuf, error: String },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProjectFormat {
+    Yaml,
+
+    Json,
+
+    Toml,
+
+    Ron,
+}
+
+impl ProjectFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .ok_or_else(|| ProjectError::UnsupportedFileFormat {
+                ext: path.file_name().unwrap().to_os_string(),
+            })?;
+
+        Self::from_extension(ext)
+    }
+
+    fn from_extension(ext: &OsStr) -> Result<Self> {
+        match ext.to_string_lossy().as_ref() {
+            "yml" | "yaml" => Ok(ProjectFormat::Yaml),
+            "json" => Ok(ProjectFormat::Json),
+            "toml" => Ok(ProjectFormat::Toml),
+            "ron" => Ok(ProjectFormat::Ron),
+            _ => bail!(ProjectError::UnsupportedFileFormat {
+                ext: ext.to_os_string(),
+            }),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ProjectFormat::Yaml => "yml",
+            ProjectFormat::Json => "json",
+            ProjectFormat::Toml => "toml",
+            ProjectFormat::Ron => "ron",
+        }
+    }
+
+    pub fn extensions() -> &'static [&'static str] {
+        &["yml", "yaml", "json", "toml", "ron"]
+    }
+
+    fn parse<T: for<'de> Deserialize<'de>>(self, content: &str) -> Res<T, String> {
+        match self {
+            ProjectFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            ProjectFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            ProjectFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ProjectFormat::Ron => ron::de::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn serialize<T: Serialize>(self, value: &T) -> Res<String, String> {
+        match self {
+            ProjectFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            ProjectFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            ProjectFormat::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            ProjectFormat::Ron => {
+                ron::ser::to_string_pretty(value, Default::default()).map_err(|e| e.to_string())
+            }
+        }
+    }
 }
 
+pub const MIN_SCHEMA_VERSION: u32 = 0;
+
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
 impl Project {
     pub fn new(name: &str) -> Self {
         Project {
             name: name.to_string(),
+            version: CURRENT_SCHEMA_VERSION,
             description: Some("An Onyx project".to_string()),
             language: None,
             container: ContainerMode::None,
@@ -158,20 +322,22 @@ impl Project {
     }
 
     pub fn load(path: &PathBuf) -> Result<Self> {
-        validate_file(path, "yml")?;
+        let format = validate_file(path)?;
         let content = read_file(path)?;
         let mut project: Project =
-            serde_yaml::from_str(&content).map_err(|err| ProjectError::FileParseError {
-                path: path.clone(),
-                error: err,
-            })?;
+            format
+                .parse(&content)
+                .map_err(|error| ProjectError::FileParseError {
+                    path: path.clone(),
+                    error,
+                })?;
         project.validate_and_normalize()?;
 
         project.included = Some(
             project
                 .include
                 .iter()
-                .map(|file| ProjectInclude::load(file))
+                .map(|file| ProjectInclude::load(file).map(|inc| WithPath::new(inc, file.clone())))
                 .collect::<Result<_>>()?,
         );
 
@@ -179,13 +345,22 @@ impl Project {
     }
 
     fn validate_and_normalize(&mut self) -> Result<()> {
+        ensure!(
+            self.version <= CURRENT_SCHEMA_VERSION,
+            "This project file declares schema version {}, but this onyx binary only \
+             understands up to version {}. Please upgrade onyx",
+            self.version,
+            CURRENT_SCHEMA_VERSION
+        );
+
         self.runner.validate_and_normalize()?;
         Ok(())
     }
 
-    pub fn merge(&self) -> Result<Self> {
-        Ok(Project {
+    pub fn merge(&self, path: &Path, overrides: &[ConfigOverride]) -> Result<Self> {
+        let mut project = Project {
             name: self.name.clone(),
+            version: self.version,
             description: self.description.clone(),
             language: self.language,
             container: self.container,
@@ -194,9 +369,17 @@ impl Project {
             included: None,
             app: {
                 let mut merged = self.app.clone();
+                let mut origins: HashMap<String, PathBuf> = HashMap::new();
+
                 if let Some(ref included) = self.included {
                     for inc in included {
-                        merged.merge(&inc.app)?;
+                        merge_config(
+                            &mut merged.config,
+                            &mut origins,
+                            inc.value.app.config.clone(),
+                            &inc.path,
+                            path,
+                        )?;
                     }
                 }
 
@@ -204,20 +387,27 @@ impl Project {
             },
             apps: {
                 let mut apps = self.apps.clone();
+                let mut app_origins: HashMap<String, HashMap<String, PathBuf>> = HashMap::new();
+
                 if let Some(ref included) = self.included {
                     for inc in included {
-                        if let Some(ref mut apps) = apps {
-                            if let Some(ref inc_apps) = inc.apps {
-                                for (name, app) in inc_apps {
-                                    if apps.contains_key(name) {
-                                        apps.get_mut(name).unwrap().merge(app)?;
-                                    } else {
-                                        apps.insert(name.to_string(), app.clone());
-                                    }
-                                }
+                        if let Some(ref inc_apps) = inc.value.apps {
+                            let apps = apps.get_or_insert_with(HashMap::new);
+
+                            for (name, app) in inc_apps {
+                                let target =
+                                    apps.entry(name.clone()).or_insert_with(Default::default);
+                                let origins = app_origins
+                                    .entry(name.clone())
+                                    .or_insert_with(HashMap::new);
+                                merge_config(
+                                    &mut target.config,
+                                    origins,
+                                    app.config.clone(),
+                                    &inc.path,
+                                    path,
+                                )?;
                             }
-                        } else {
-                            apps = inc.apps.clone();
                         }
                     }
                 }
@@ -227,49 +417,198 @@ impl Project {
             runner: {
                 let mut runner = Runner {
                     valid: self.runner.valid.clone(),
-                    default: {
-                        if let Some(ref included) = self.included {
-                            let mut reversed = included.clone();
-                            reversed.reverse();
-
-                            let last = reversed.iter().find(|inc| inc.runner.is_some());
-                            if let Some(ref last) = last {
-                                last.runner.as_ref().unwrap().default.clone()
-                            } else {
-                                self.runner.default.clone()
-                            }
-                        } else {
-                            self.runner.default.clone()
-                        }
-                    },
+                    default: self.runner.default.clone(),
                 };
 
+                if let Some(ref included) = self.included {
+                    for inc in included {
+                        if let Some(ref runner_include) = inc.value.runner {
+                            let other = Runner {
+                                valid: vec![],
+                                default: runner_include.default.clone(),
+                            };
+                            let ctx = MergeCtx::new(path, &inc.path);
+                            runner.merge(other, &ctx)?;
+                        }
+                    }
+                }
+
                 runner.validate_and_normalize()?;
 
                 runner
             },
-        })
+        };
+
+        for over in overrides {
+            match &over.app {
+                Some(app_name) => {
+                    let apps = project
+                        .apps
+                        .as_mut()
+                        .ok_or_else(|| format_err!("Unknown app: {}", app_name))?;
+                    let app = apps
+                        .get_mut(app_name)
+                        .ok_or_else(|| format_err!("Unknown app: {}", app_name))?;
+                    app.apply_override(&over.key, &over.sub_key, &over.value);
+                }
+                None => {
+                    project.app.apply_override(&over.key, &over.sub_key, &over.value);
+                }
+            }
+        }
+
+        Ok(project)
     }
 
     pub fn get_config(
         &self,
-        _app: &Option<String>,
-        _key: &String,
-        _sub_key: &Option<String>,
+        app: &Option<String>,
+        key: &str,
+        sub_key: &Option<String>,
     ) -> Result<ConfigSearch> {
-        Ok(ConfigSearch::Single(Config::Single(ConfigValue::from(""))))
+        let app = if self.umbrella { app.as_ref() } else { None };
+
+        match app {
+            Some(app_name) => self.application(app_name)?.get_config(key, sub_key),
+            None => match self.apps {
+                Some(ref apps) => {
+                    let mut result = HashMap::new();
+                    for (name, application) in apps {
+                        match application.get_config(key, sub_key)? {
+                            ConfigSearch::Single(config) => {
+                                result.insert(name.to_string(), config);
+                            }
+                            ConfigSearch::Multiple(_) => bail!(
+                                "Key `{}` does not contain key-value pairs in app `{}`",
+                                key,
+                                name
+                            ),
+                        }
+                    }
+
+                    Ok(ConfigSearch::Multiple(result))
+                }
+                None => self.app.get_config(key, sub_key),
+            },
+        }
+    }
+
+    fn application(&self, app_name: &str) -> Result<&Application> {
+        self.apps
+            .as_ref()
+            .and_then(|apps| apps.get(app_name))
+            .ok_or_else(|| format_err!("Unknown app: {}", app_name))
+    }
+
+    pub fn to_dotenv(&self, app: &Option<String>, separator: &str) -> Result<String> {
+        let mut lines = vec![];
+        let app = if self.umbrella { app.as_ref() } else { None };
+
+        match app {
+            Some(app_name) => {
+                flatten_application(self.application(app_name)?, None, separator, &mut lines)
+            }
+            None => match self.apps {
+                Some(ref apps) => {
+                    let mut names: Vec<_> = apps.keys().collect();
+                    names.sort();
+
+                    for name in names {
+                        flatten_application(&apps[name], Some(name), separator, &mut lines);
+                    }
+                }
+                None => flatten_application(&self.app, None, separator, &mut lines),
+            },
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+// `origins` tracks which file last wrote each key, so a conflict is blamed on the include that
+// actually introduced the value rather than always on the root project file.
+fn merge_config(
+    target: &mut Option<HashMap<String, Config>>,
+    origins: &mut HashMap<String, PathBuf>,
+    other: Option<HashMap<String, Config>>,
+    other_path: &Path,
+    root_path: &Path,
+) -> Result<()> {
+    let other = match other {
+        Some(other) => other,
+        None => return Ok(()),
+    };
+
+    let map = target.get_or_insert_with(HashMap::new);
+
+    for (key, val) in other {
+        if let Some(mut existing) = map.remove(&key) {
+            let self_path = origins
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| root_path.to_path_buf());
+            let ctx = MergeCtx::new(&self_path, other_path).with_key(&key);
+            existing.merge(val, &ctx)?;
+            map.insert(key.clone(), existing);
+        } else {
+            map.insert(key.clone(), val);
+        }
+
+        origins.insert(key, other_path.to_path_buf());
+    }
+
+    Ok(())
+}
+
+fn flatten_application(
+    application: &Application,
+    app_name: Option<&str>,
+    separator: &str,
+    lines: &mut Vec<String>,
+) {
+    let config = match application.config {
+        Some(ref config) => config,
+        None => return,
+    };
+
+    let mut keys: Vec<_> = config.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let prefix = match app_name {
+            Some(app_name) => format!("{}{}{}", app_name, separator, key),
+            None => key.to_string(),
+        };
+        flatten_config(&config[key], &prefix, separator, lines);
+    }
+}
+
+fn flatten_config(config: &Config, prefix: &str, separator: &str, lines: &mut Vec<String>) {
+    match config {
+        Config::Single(value) => lines.push(format!("{}={}", prefix.to_uppercase(), value)),
+        Config::Map(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+
+            for key in keys {
+                let full_key = format!("{}{}{}", prefix, separator, key);
+                lines.push(format!("{}={}", full_key.to_uppercase(), map[key]));
+            }
+        }
     }
 }
 
 impl ProjectInclude {
     pub fn load(path: &PathBuf) -> Result<Self> {
-        validate_file(path, "yml")?;
+        let format = validate_file(path)?;
         let content = read_file(path)?;
         let include: ProjectInclude =
-            serde_yaml::from_str(&content).map_err(|err| ProjectError::FileParseError {
-                path: path.clone(),
-                error: err,
-            })?;
+            format
+                .parse(&content)
+                .map_err(|error| ProjectError::FileParseError {
+                    path: path.clone(),
+                    error,
+                })?;
 
         Ok(include)
     }
@@ -286,58 +625,83 @@ impl Application {
         self.config.is_none()
     }
 
-    fn merge(&mut self, other: &Application) -> Result<()> {
-        self.config = {
-            let mut merged = self.config.clone();
+    fn apply_override(&mut self, key: &str, sub_key: &Option<String>, value: &str) {
+        let config = self.config.get_or_insert_with(HashMap::new);
 
-            if let Some(ref config) = other.config {
-                if let Some(ref mut m) = merged {
-                    for (key, val) in config {
-                        if m.contains_key(key) {
-                            let mut m_val = m[key].clone();
-                            m_val.merge(val, key)?;
-                            m.insert(key.to_string(), m_val);
-                        } else {
-                            m.insert(key.to_string(), val.clone());
-                        }
-                    }
-                } else {
-                    merged = Some(config.clone());
-                }
+        match sub_key {
+            Some(sub_key) => {
+                let mut map = match config.remove(key) {
+                    Some(Config::Map(map)) => map,
+                    _ => HashMap::new(),
+                };
+                map.insert(sub_key.to_string(), value.into());
+                config.insert(key.to_string(), Config::Map(map));
+            }
+            None => {
+                config.insert(key.to_string(), Config::Single(value.into()));
             }
+        }
+    }
 
-            merged
-        };
-        Ok(())
+    fn get_config(&self, key: &str, sub_key: &Option<String>) -> Result<ConfigSearch> {
+        let config = self
+            .config
+            .as_ref()
+            .and_then(|config| config.get(key))
+            .ok_or_else(|| format_err!("Key not found: {}", key))?;
+
+        match sub_key {
+            None => Ok(ConfigSearch::Single(config.clone())),
+            Some(sub_key) => match config {
+                Config::Single(_) => {
+                    bail!("Key `{}` does not contain key-value pairs", key)
+                }
+                Config::Map(map) => {
+                    let value = map
+                        .get(sub_key)
+                        .ok_or_else(|| format_err!("Sub-key not found: {}", sub_key))?;
+                    Ok(ConfigSearch::Single(Config::Single(value.clone())))
+                }
+            },
+        }
     }
 }
 
-impl Config {
-    fn merge(&mut self, other: &Config, key: &str) -> Result<()> {
+impl Merge for Config {
+    fn merge(&mut self, other: Self, ctx: &MergeCtx) -> Result<()> {
         use self::ProjectError::IncompatibleConfigType;
         use Config::*;
 
         let merged;
+        let key = ctx.key.clone().unwrap_or_default();
 
         match other {
             Map(other) => match self {
                 Map(map) => {
                     for (key, val) in other {
-                        map.insert(key.to_string(), val.clone());
+                        map.insert(key, val);
                     }
 
                     merged = Map(map.clone());
                 }
                 Single(_) => bail!(IncompatibleConfigType {
-                    key: key.to_string()
+                    key,
+                    self_kind: "string",
+                    self_path: ctx.self_path.to_path_buf(),
+                    other_kind: "map",
+                    other_path: ctx.other_path.to_path_buf(),
                 }),
             },
             Single(other) => match self {
                 Map(_) => bail!(IncompatibleConfigType {
-                    key: key.to_string()
+                    key,
+                    self_kind: "map",
+                    self_path: ctx.self_path.to_path_buf(),
+                    other_kind: "string",
+                    other_path: ctx.other_path.to_path_buf(),
                 }),
                 Single(_) => {
-                    merged = Single(other.clone());
+                    merged = Single(other);
                 }
             },
         }
@@ -433,6 +797,13 @@ impl Serialize for ConfigValue {
     }
 }
 
+impl Merge for Runner {
+    fn merge(&mut self, other: Self, _ctx: &MergeCtx) -> Result<()> {
+        self.default = other.default;
+        Ok(())
+    }
+}
+
 impl Runner {
     fn validate_and_normalize(&mut self) -> Result<()> {
         self.default = self
@@ -587,27 +958,34 @@ impl<'de> Deserialize<'de> for RunnerEntry {
     }
 }
 
-fn validate_file(path: &PathBuf, extension: &str) -> Result<()> {
-    use self::ProjectError::*;
+pub fn find_project_file(start: &Path, stem: &str, extensions: &[&str]) -> Result<PathBuf> {
+    let mut dir = start.to_path_buf();
 
-    let metadata = fs::metadata(path)?;
-    ensure!(metadata.is_file(), "{:?} is not a file.", path);
-    ensure!(
-        path.extension().is_some(),
-        UnsupportedFileFormat {
-            ext: path.file_name().unwrap().to_os_string(),
+    loop {
+        for ext in extensions {
+            let candidate = dir.join(format!("{}.{}", stem, ext));
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
         }
-    );
 
-    let ext = path.extension().unwrap();
-    ensure!(
-        ext == OsString::from(extension),
-        UnsupportedFileFormat {
-            ext: OsString::from(ext),
+        match dir.parent() {
+            Some(parent) => dir = parent.to_path_buf(),
+            None => bail!(
+                "Could not find {}.{{{}}} in {:?} or any parent directory",
+                stem,
+                extensions.join(","),
+                start
+            ),
         }
-    );
+    }
+}
 
-    Ok(())
+fn validate_file(path: &PathBuf) -> Result<ProjectFormat> {
+    let metadata = fs::metadata(path)?;
+    ensure!(metadata.is_file(), "{:?} is not a file.", path);
+
+    ProjectFormat::from_path(path)
 }
 
 fn validate_file_not_exists(path: &PathBuf) -> Result<()> {
@@ -630,6 +1008,7 @@ fn validate_file_not_exists(path: &PathBuf) -> Result<()> {
 
 pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
     validate_file_not_exists(file)?;
+    let format = ProjectFormat::from_path(file)?;
 
     let mut project = if let Some(name) = name {
         Project::new(&name)
@@ -637,16 +1016,20 @@ pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
         let name: String = prompt("Name of the project");
         let description = prompt_default("Description", "".to_string());
         let umbrella = prompt_default("Umbrella", false);
-        let include_file = prompt_default("Generate and include a onyx.priv.yml file?", true);
+        let include_file = prompt_default(
+            &format!("Generate and include a onyx.priv.{} file?", format.extension()),
+            true,
+        );
 
         Project {
             name,
+            version: CURRENT_SCHEMA_VERSION,
             description: Some(description),
             language: None,
             container: ContainerMode::None,
             umbrella,
             include: if include_file {
-                vec![PathBuf::from("onyx.priv.yml")]
+                vec![PathBuf::from(format!("onyx.priv.{}", format.extension()))]
             } else {
                 vec![]
             },
@@ -680,7 +1063,12 @@ pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
     project.app.config = Some(config);
 
     debug!("Generated file: {:#?}", project);
-    let serialized = serde_yaml::to_string(&project)?;
+    let serialized = format
+        .serialize(&project)
+        .map_err(|error| ProjectError::FileParseError {
+            path: file.clone(),
+            error,
+        })?;
     let mut output = File::create(file)?;
     output.write(serialized.as_bytes())?;
 
@@ -689,7 +1077,7 @@ pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
     if project.include.len() > 0 {
         for file in &project.include {
             validate_file_not_exists(file)?;
-            let mut included = ProjectInclude {
+            let included = ProjectInclude {
                 app: Application {
                     config: {
                         let mut db = HashMap::new();
@@ -704,7 +1092,14 @@ pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
             };
 
             debug!("Generated include file: {:#?}", included);
-            let serialized = serde_yaml::to_string(&included)?;
+            let include_format = ProjectFormat::from_path(file)?;
+            let serialized =
+                include_format
+                    .serialize(&included)
+                    .map_err(|error| ProjectError::FileParseError {
+                        path: file.clone(),
+                        error,
+                    })?;
             let mut output = File::create(file)?;
             output.write(serialized.as_bytes())?;
         }
@@ -717,6 +1112,266 @@ pub fn init(file: &PathBuf, name: &Option<String>) -> Result<()> {
 
 impl fmt::Display for ConfigSearch {
     fn fmt(&self, f: &mut fmt::Formatter) -> Res<(), fmt::Error> {
-        f.write_str("example")
+        match self {
+            ConfigSearch::Single(config) => write!(f, "{}", config),
+            ConfigSearch::Multiple(configs) => {
+                let mut keys: Vec<_> = configs.keys().collect();
+                keys.sort();
+
+                let formatted: Vec<String> = keys
+                    .iter()
+                    .map(|key| format!("{}: {}", key, configs[*key]))
+                    .collect();
+                write!(f, "{}", formatted.join("\n"))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Res<(), fmt::Error> {
+        match self {
+            Config::Single(value) => write!(f, "{}", value),
+            Config::Map(map) => {
+                let mut keys: Vec<_> = map.keys().collect();
+                keys.sort();
+
+                let formatted: Vec<String> = keys
+                    .iter()
+                    .map(|key| format!("{}: {}", key, map[*key]))
+                    .collect();
+                write!(f, "{}", formatted.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Res<(), fmt::Error> {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_and_normalize_accepts_default_and_current_schema_versions() {
+        let mut project = Project::new("test");
+        project.version = 0;
+        assert!(project.validate_and_normalize().is_ok());
+
+        let mut project = Project::new("test");
+        project.version = CURRENT_SCHEMA_VERSION;
+        assert!(project.validate_and_normalize().is_ok());
+    }
+
+    #[test]
+    fn validate_and_normalize_rejects_a_newer_schema_version() {
+        let mut project = Project::new("test");
+        project.version = CURRENT_SCHEMA_VERSION + 1;
+        assert!(project.validate_and_normalize().is_err());
+    }
+
+    fn sample_project(
+        umbrella: bool,
+        app: Application,
+        apps: Option<HashMap<String, Application>>,
+    ) -> Project {
+        Project {
+            name: "test".to_string(),
+            version: CURRENT_SCHEMA_VERSION,
+            description: None,
+            language: None,
+            container: ContainerMode::None,
+            umbrella,
+            include: vec![],
+            included: None,
+            app,
+            apps,
+            runner: Default::default(),
+        }
+    }
+
+    #[test]
+    fn flatten_config_joins_nested_keys_with_custom_separator() {
+        let mut map = HashMap::new();
+        map.insert("host".to_string(), "localhost".into());
+        map.insert("port".to_string(), "5432".into());
+
+        let mut lines = vec![];
+        flatten_config(&Config::Map(map), "db", "::", &mut lines);
+        lines.sort();
+
+        assert_eq!(
+            lines,
+            vec!["DB::HOST=localhost".to_string(), "DB::PORT=5432".to_string()]
+        );
+    }
+
+    #[test]
+    fn to_dotenv_flattens_root_app_when_not_umbrella() {
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), Config::Single("value".into()));
+        let project = sample_project(false, Application { config: Some(config) }, None);
+
+        let dotenv = project.to_dotenv(&None, "_").unwrap();
+        assert_eq!(dotenv, "KEY=value");
+    }
+
+    #[test]
+    fn to_dotenv_aggregates_every_app_when_umbrella_and_no_app_given() {
+        let mut api_config = HashMap::new();
+        api_config.insert("port".to_string(), Config::Single("8080".into()));
+        let mut worker_config = HashMap::new();
+        worker_config.insert("port".to_string(), Config::Single("9090".into()));
+
+        let mut apps = HashMap::new();
+        apps.insert(
+            "api".to_string(),
+            Application { config: Some(api_config) },
+        );
+        apps.insert(
+            "worker".to_string(),
+            Application { config: Some(worker_config) },
+        );
+        let project = sample_project(true, Default::default(), Some(apps));
+
+        let dotenv = project.to_dotenv(&None, "_").unwrap();
+        assert_eq!(dotenv, "API_PORT=8080\nWORKER_PORT=9090");
+    }
+
+    #[test]
+    fn to_dotenv_flattens_single_app_when_app_given() {
+        let mut api_config = HashMap::new();
+        api_config.insert("port".to_string(), Config::Single("8080".into()));
+        let mut apps = HashMap::new();
+        apps.insert(
+            "api".to_string(),
+            Application { config: Some(api_config) },
+        );
+        let project = sample_project(true, Default::default(), Some(apps));
+
+        let dotenv = project.to_dotenv(&Some("api".to_string()), "_").unwrap();
+        assert_eq!(dotenv, "PORT=8080");
+    }
+
+    #[test]
+    fn find_project_file_errors_when_filesystem_root_is_reached() {
+        let stem = format!("onyx-missing-{}", std::process::id());
+        let dir = std::env::temp_dir();
+
+        let result = find_project_file(&dir, &stem, ProjectFormat::extensions());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn find_project_file_discovers_non_yml_extensions() {
+        let base = std::env::temp_dir().join(format!("onyx-find-test-{}", std::process::id()));
+        let nested = base.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(base.join("onyx.toml"), "").unwrap();
+
+        let found = find_project_file(&nested, "onyx", ProjectFormat::extensions()).unwrap();
+        assert_eq!(found, base.join("onyx.toml"));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn config_override_parses_app_qualified_key_without_sub_key() {
+        let over: ConfigOverride = "myapp:port=9999".parse().unwrap();
+        assert_eq!(over.app, Some("myapp".to_string()));
+        assert_eq!(over.key, "port");
+        assert_eq!(over.sub_key, None);
+        assert_eq!(over.value, "9999");
+    }
+
+    #[test]
+    fn config_override_parses_app_qualified_key_with_sub_key() {
+        let over: ConfigOverride = "myapp:db.port=9999".parse().unwrap();
+        assert_eq!(over.app, Some("myapp".to_string()));
+        assert_eq!(over.key, "db");
+        assert_eq!(over.sub_key, Some("port".to_string()));
+        assert_eq!(over.value, "9999");
+    }
+
+    #[test]
+    fn config_override_parses_default_app_key_with_sub_key() {
+        let over: ConfigOverride = "db.port=9999".parse().unwrap();
+        assert_eq!(over.app, None);
+        assert_eq!(over.key, "db");
+        assert_eq!(over.sub_key, Some("port".to_string()));
+        assert_eq!(over.value, "9999");
+    }
+
+    #[test]
+    fn merge_config_attributes_conflict_to_the_include_that_introduced_it() {
+        let mut target: Option<HashMap<String, Config>> = None;
+        let mut origins = HashMap::new();
+        let root_path = Path::new("onyx.yml");
+        let a_path = Path::new("a.yml");
+        let b_path = Path::new("b.yml");
+
+        let mut from_a = HashMap::new();
+        from_a.insert("db".to_string(), Config::Single("localhost".into()));
+        merge_config(&mut target, &mut origins, Some(from_a), a_path, root_path).unwrap();
+
+        let mut db = HashMap::new();
+        db.insert("host".to_string(), "localhost".into());
+        let mut from_b = HashMap::new();
+        from_b.insert("db".to_string(), Config::Map(db));
+        let error =
+            merge_config(&mut target, &mut origins, Some(from_b), b_path, root_path).unwrap_err();
+
+        let message = error.to_string();
+        assert!(message.contains("a.yml"), "{}", message);
+        assert!(!message.contains("onyx.yml"), "{}", message);
+    }
+
+    fn round_trips(format: ProjectFormat) {
+        let mut config = HashMap::new();
+        config.insert("key".to_string(), Config::Single("value".into()));
+
+        let project = Project {
+            name: "sample".to_string(),
+            version: CURRENT_SCHEMA_VERSION,
+            description: None,
+            language: None,
+            container: ContainerMode::None,
+            umbrella: false,
+            include: vec![],
+            included: None,
+            app: Application {
+                config: Some(config),
+            },
+            apps: None,
+            runner: Default::default(),
+        };
+
+        let serialized = format.serialize(&project).unwrap();
+        let parsed: Project = format.parse(&serialized).unwrap();
+        assert_eq!(project, parsed);
+    }
+
+    #[test]
+    fn project_format_round_trips_yaml() {
+        round_trips(ProjectFormat::Yaml);
+    }
+
+    #[test]
+    fn project_format_round_trips_json() {
+        round_trips(ProjectFormat::Json);
+    }
+
+    #[test]
+    fn project_format_round_trips_toml() {
+        round_trips(ProjectFormat::Toml);
+    }
+
+    #[test]
+    fn project_format_round_trips_ron() {
+        round_trips(ProjectFormat::Ron);
     }
 }